@@ -0,0 +1,125 @@
+//! Converts the various Python call shapes users pass cash flows in (two
+//! parallel sequences, a dict, an iterable of pairs, numpy arrays, ...) into
+//! the `Vec<Payment>` the solvers in [`crate::core`] operate on.
+
+use chrono::NaiveDate;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+use xirr::Payment;
+
+use crate::dates::DateLike;
+
+/// Extracts a `NaiveDate` from a single Python object: `datetime.date`, an
+/// ISO string, an integer day count, or a numpy `datetime64` scalar. See
+/// `DateLike` for the conversion rules.
+pub fn extract_date(obj: &PyAny) -> PyResult<NaiveDate> {
+    obj.extract::<DateLike>().map(|d| d.0)
+}
+
+fn payment_from_pair(pair: &PyAny) -> PyResult<Payment> {
+    Ok(Payment {
+        date: extract_date(pair.get_item(0)?)?,
+        amount: pair.get_item(1)?.extract::<f64>()?,
+    })
+}
+
+fn zip_payments(dates: &PyAny, amounts: &PyAny) -> PyResult<Vec<Payment>> {
+    dates
+        .iter()?
+        .zip(amounts.iter()?)
+        .map(|(date, amount)| {
+            Ok(Payment {
+                date: extract_date(date?)?,
+                amount: amount?.extract::<f64>()?,
+            })
+        })
+        .collect()
+}
+
+/// Builds the `Vec<Payment>` that the solvers need from whatever shape of
+/// Python arguments the caller passed in.
+///
+/// Accepts, in order of precedence:
+/// - two parallel sequences: `to_payments(dates, Some(amounts))`, including
+///   numpy arrays (iterated like any other sequence; extracting an `f64`
+///   from a numpy scalar is free)
+/// - a single `dict` mapping date -> amount
+/// - a single iterable of `(date, amount)` pairs, which also covers
+///   `zip(dates, amounts)`
+///
+/// The result is sorted by date, since the solvers assume the first payment
+/// is the earliest one.
+pub fn to_payments(arg1: &PyAny, arg2: Option<&PyAny>) -> PyResult<Vec<Payment>> {
+    let mut payments = if let Some(amounts) = arg2 {
+        zip_payments(arg1, amounts)?
+    } else if let Ok(dict) = arg1.downcast::<PyDict>() {
+        dict.items()
+            .iter()
+            .map(payment_from_pair)
+            .collect::<PyResult<Vec<_>>>()?
+    } else {
+        arg1.iter()?
+            .map(|item| payment_from_pair(item?))
+            .collect::<PyResult<Vec<_>>>()?
+    };
+
+    payments.sort_by_key(|p| p.date);
+    Ok(payments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::Python;
+
+    #[test]
+    fn test_to_payments_from_two_sequences() {
+        Python::with_gil(|py| {
+            let dates = py.eval("['2024-01-01', '2024-06-01']", None, None).unwrap();
+            let amounts = py.eval("[-1000.0, 1100.0]", None, None).unwrap();
+
+            let payments = to_payments(dates, Some(amounts)).unwrap();
+
+            assert_eq!(payments.len(), 2);
+            assert_eq!(payments[0].date, NaiveDate::from_ymd(2024, 1, 1));
+            assert_eq!(payments[0].amount, -1000.0);
+            assert_eq!(payments[1].date, NaiveDate::from_ymd(2024, 6, 1));
+            assert_eq!(payments[1].amount, 1100.0);
+        });
+    }
+
+    #[test]
+    fn test_to_payments_from_dict() {
+        Python::with_gil(|py| {
+            let dict = py
+                .eval("{'2024-06-01': 1100.0, '2024-01-01': -1000.0}", None, None)
+                .unwrap();
+
+            let payments = to_payments(dict, None).unwrap();
+
+            // Sorted by date, regardless of dict insertion order.
+            assert_eq!(payments.len(), 2);
+            assert_eq!(payments[0].date, NaiveDate::from_ymd(2024, 1, 1));
+            assert_eq!(payments[1].date, NaiveDate::from_ymd(2024, 6, 1));
+        });
+    }
+
+    #[test]
+    fn test_to_payments_from_zip_of_pairs() {
+        Python::with_gil(|py| {
+            let zipped = py
+                .eval(
+                    "zip(['2024-01-01', '2024-06-01'], [-1000.0, 1100.0])",
+                    None,
+                    None,
+                )
+                .unwrap();
+
+            let payments = to_payments(zipped, None).unwrap();
+
+            assert_eq!(payments.len(), 2);
+            assert_eq!(payments[0].date, NaiveDate::from_ymd(2024, 1, 1));
+            assert_eq!(payments[1].amount, 1100.0);
+        });
+    }
+}
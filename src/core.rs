@@ -0,0 +1,485 @@
+//! Pure financial-math routines, independent of the Python bindings.
+//!
+//! Kept separate from `lib.rs` so none of this needs `pyo3`; the bindings
+//! layer is responsible for pulling arguments out of Python objects and
+//! translating `FinanceError` into `InvalidPaymentsError`.
+
+use std::collections::BTreeMap;
+
+use xirr::Payment;
+
+use crate::day_count::DayCount;
+
+/// Amounts smaller than this (in absolute value) are treated as noise and
+/// dropped by `clean_payments`.
+const AMOUNT_EPSILON: f64 = 1e-6;
+
+/// Structurally invalid input (empty payments, no sign change, ...) is kept
+/// distinct from a solver simply failing to converge so that callers like
+/// `xirr_rust`'s `silent` mode can raise on the former but swallow the
+/// latter.
+#[derive(Debug)]
+pub enum FinanceError {
+    InvalidInput(String),
+    DidNotConverge(String),
+}
+
+impl std::fmt::Display for FinanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FinanceError::InvalidInput(msg) => write!(f, "{}", msg),
+            FinanceError::DidNotConverge(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+pub type FinanceResult<T> = Result<T, FinanceError>;
+
+/// Net present value of `amounts`, discounted at `rate` one per integer
+/// period starting at t = 0.
+pub fn npv(rate: f64, amounts: &[f64]) -> f64 {
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(t, amount)| amount / (1.0 + rate).powi(t as i32))
+        .sum()
+}
+
+/// Net present value of dated `payments`, discounted at `rate` using
+/// `day_count` to turn each date's gap from the first payment into a year
+/// fraction.
+pub fn xnpv(rate: f64, payments: &[Payment], day_count: DayCount) -> FinanceResult<f64> {
+    let first = payments
+        .first()
+        .ok_or_else(|| FinanceError::InvalidInput("payments must not be empty".to_string()))?
+        .date;
+
+    Ok(payments
+        .iter()
+        .map(|p| {
+            let t = day_count.year_fraction(first, p.date);
+            p.amount / (1.0 + rate).powf(t)
+        })
+        .sum())
+}
+
+const MAX_ITER: u32 = 100;
+const TOLERANCE: f64 = 1e-9;
+
+/// Newton's method with a central-difference derivative. Good enough for the
+/// well-behaved annuity equations below; `xirr` gets a hardened solver of its
+/// own since irregular cash flows are more prone to non-convergence.
+fn newton<F>(f: F, guess: f64) -> FinanceResult<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut x = guess;
+
+    for _ in 0..MAX_ITER {
+        let fx = f(x);
+        if fx.abs() < TOLERANCE {
+            return Ok(x);
+        }
+
+        let h = 1e-6;
+        let derivative = (f(x + h) - f(x - h)) / (2.0 * h);
+        if derivative.abs() < TOLERANCE {
+            return Err(FinanceError::DidNotConverge("derivative vanished during root search".to_string()));
+        }
+
+        x -= fx / derivative;
+    }
+
+    Err(FinanceError::DidNotConverge("root finding did not converge".to_string()))
+}
+
+/// Rate at which `npv(rate, amounts) == 0`.
+pub fn irr(amounts: &[f64]) -> FinanceResult<f64> {
+    if amounts.is_empty() {
+        return Err(FinanceError::InvalidInput("amounts must not be empty".to_string()));
+    }
+
+    newton(|r| npv(r, amounts), 0.1)
+}
+
+/// Rate at which `xnpv(rate, payments) == 0`, starting the search from
+/// `guess` and using `day_count` to turn dates into year fractions.
+///
+/// Cash flows from real users are often irregular enough that plain Newton's
+/// method fails to converge, so this uses the analytic derivative
+/// `f'(r) = Σ -tᵢ·amountᵢ / (1+r)^(tᵢ+1)` and falls back to bisection over a
+/// bracket found by scanning rates outward from -0.999 whenever Newton's
+/// iterate leaves `(-1, ∞)` or the derivative is too close to zero to trust.
+pub fn xirr(payments: &[Payment], guess: f64, day_count: DayCount) -> FinanceResult<f64> {
+    let first = payments
+        .first()
+        .ok_or_else(|| FinanceError::InvalidInput("payments must not be empty".to_string()))?
+        .date;
+
+    let years: Vec<f64> = payments
+        .iter()
+        .map(|p| day_count.year_fraction(first, p.date))
+        .collect();
+    let amounts: Vec<f64> = payments.iter().map(|p| p.amount).collect();
+
+    let f = |r: f64| -> f64 {
+        years
+            .iter()
+            .zip(&amounts)
+            .map(|(t, a)| a / (1.0 + r).powf(*t))
+            .sum()
+    };
+
+    let df = |r: f64| -> f64 {
+        years
+            .iter()
+            .zip(&amounts)
+            .map(|(t, a)| -t * a / (1.0 + r).powf(t + 1.0))
+            .sum()
+    };
+
+    newton_with_derivative(&f, &df, guess).or_else(|_| bisect_bracket(&f))
+}
+
+fn newton_with_derivative<F, D>(f: F, df: D, guess: f64) -> FinanceResult<f64>
+where
+    F: Fn(f64) -> f64,
+    D: Fn(f64) -> f64,
+{
+    let mut r = guess;
+
+    for _ in 0..MAX_ITER {
+        let fr = f(r);
+        if fr.abs() < TOLERANCE {
+            return Ok(r);
+        }
+
+        let dr = df(r);
+        if dr.abs() < TOLERANCE {
+            return Err(FinanceError::DidNotConverge("derivative vanished during Newton iteration".to_string()));
+        }
+
+        let next = r - fr / dr;
+        if !next.is_finite() || next <= -1.0 {
+            return Err(FinanceError::DidNotConverge("Newton iterate left (-1, inf)".to_string()));
+        }
+
+        r = next;
+    }
+
+    Err(FinanceError::DidNotConverge("Newton's method did not converge".to_string()))
+}
+
+/// Scans outward from -0.999 in expanding steps looking for a sign change in
+/// `f`, then bisects the resulting bracket.
+fn bisect_bracket<F>(f: F) -> FinanceResult<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut lo = -0.999;
+    let mut f_lo = f(lo);
+    let mut step = 0.1;
+    let mut hi = lo;
+
+    loop {
+        hi += step;
+        let f_hi = f(hi);
+        if f_lo * f_hi <= 0.0 {
+            break;
+        }
+
+        lo = hi;
+        f_lo = f_hi;
+        step *= 2.0;
+
+        if hi > 1e10 {
+            return Err(FinanceError::InvalidInput("failed to bracket a root for xirr".to_string()));
+        }
+    }
+
+    let mut a = lo;
+    let mut b = hi;
+    let mut fa = f(a);
+
+    for _ in 0..MAX_ITER {
+        let mid = (a + b) / 2.0;
+        let fm = f(mid);
+
+        if fm.abs() < TOLERANCE || (b - a).abs() < TOLERANCE {
+            return Ok(mid);
+        }
+
+        if fa.signum() == fm.signum() {
+            a = mid;
+            fa = fm;
+        } else {
+            b = mid;
+        }
+    }
+
+    Err(FinanceError::DidNotConverge("bisection did not converge".to_string()))
+}
+
+fn type_factor(pay_type: i32, rate: f64) -> f64 {
+    1.0 + rate * pay_type as f64
+}
+
+/// Future value implied by the annuity relation
+/// `pv·(1+r)ⁿ + pmt·(1+r·type)·((1+r)ⁿ−1)/r + fv = 0`.
+pub fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, pay_type: i32) -> f64 {
+    if rate == 0.0 {
+        return -(pv + pmt * nper);
+    }
+
+    let growth = (1.0 + rate).powf(nper);
+    -(pv * growth + pmt * type_factor(pay_type, rate) * (growth - 1.0) / rate)
+}
+
+/// Present value implied by the same annuity relation as [`fv`].
+pub fn pv(rate: f64, nper: f64, pmt: f64, fv: f64, pay_type: i32) -> f64 {
+    if rate == 0.0 {
+        return -(fv + pmt * nper);
+    }
+
+    let growth = (1.0 + rate).powf(nper);
+    -(fv + pmt * type_factor(pay_type, rate) * (growth - 1.0) / rate) / growth
+}
+
+/// Payment per period implied by the same annuity relation as [`fv`].
+pub fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, pay_type: i32) -> f64 {
+    if rate == 0.0 {
+        return -(fv + pv) / nper;
+    }
+
+    let growth = (1.0 + rate).powf(nper);
+    -(fv + pv * growth) * rate / (type_factor(pay_type, rate) * (growth - 1.0))
+}
+
+/// Number of periods implied by the same annuity relation as [`fv`].
+pub fn nper(rate: f64, pmt: f64, pv: f64, fv: f64, pay_type: i32) -> f64 {
+    if rate == 0.0 {
+        return -(fv + pv) / pmt;
+    }
+
+    let factor = type_factor(pay_type, rate);
+    ((pmt * factor - fv * rate) / (pmt * factor + pv * rate)).ln() / (1.0 + rate).ln()
+}
+
+/// Rate implied by the same annuity relation as [`fv`]; unlike the others
+/// this has no closed form, so it is found with Newton's method.
+pub fn rate(nper: f64, pmt: f64, pv: f64, fv_target: f64, pay_type: i32, guess: f64) -> FinanceResult<f64> {
+    newton(|r| fv(r, nper, pmt, pv, pay_type) - fv_target, guess)
+}
+
+/// Groups `payments` by date, summing amounts posted on the same day, then
+/// drops any resulting amount too small to matter. Used by the lenient
+/// `xirr_clean` entry point, which otherwise treats repeated dates and
+/// near-zero noise as ordinary input rather than structurally invalid.
+pub fn clean_payments(payments: Vec<Payment>) -> Vec<Payment> {
+    let mut by_date: BTreeMap<chrono::NaiveDate, f64> = BTreeMap::new();
+
+    for payment in payments {
+        *by_date.entry(payment.date).or_insert(0.0) += payment.amount;
+    }
+
+    by_date
+        .into_iter()
+        .filter(|(_, amount)| amount.abs() >= AMOUNT_EPSILON)
+        .map(|(date, amount)| Payment { date, amount })
+        .collect()
+}
+
+/// Modified IRR: discounts negative cash flows at `finance_rate`, compounds
+/// positive cash flows forward at `reinvest_rate`, and annualizes the ratio.
+pub fn mirr(amounts: &[f64], finance_rate: f64, reinvest_rate: f64) -> FinanceResult<f64> {
+    let n = amounts.len();
+    if n < 2 {
+        return Err(FinanceError::InvalidInput("mirr requires at least two payments".to_string()));
+    }
+
+    let negative_pv: f64 = amounts
+        .iter()
+        .enumerate()
+        .filter(|(_, &a)| a < 0.0)
+        .map(|(t, a)| a / (1.0 + finance_rate).powi(t as i32))
+        .sum();
+
+    let positive_fv: f64 = amounts
+        .iter()
+        .enumerate()
+        .filter(|(_, &a)| a > 0.0)
+        .map(|(t, a)| a * (1.0 + reinvest_rate).powi((n - 1 - t) as i32))
+        .sum();
+
+    if negative_pv == 0.0 || positive_fv == 0.0 {
+        return Err(FinanceError::InvalidInput(
+            "mirr requires both positive and negative payments".to_string(),
+        ));
+    }
+
+    Ok((-positive_fv / negative_pv).powf(1.0 / (n - 1) as f64) - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_irr_rejects_empty_input() {
+        assert!(irr(&[]).is_err());
+    }
+
+    #[test]
+    fn test_xirr_falls_back_to_bisection_from_a_bad_guess() {
+        let payments = vec![
+            Payment {
+                date: NaiveDate::from_ymd(2020, 1, 1),
+                amount: -1000.0,
+            },
+            Payment {
+                date: NaiveDate::from_ymd(2021, 1, 1),
+                amount: 1100.0,
+            },
+        ];
+
+        // A wildly bad guess sends Newton's first iterate far below -1,
+        // forcing the bisection fallback to do the work.
+        let rate = xirr(&payments, 1_000.0, DayCount::Act365F).unwrap();
+        assert!((rate - 0.1).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_clean_payments_aggregates_duplicate_dates() {
+        let payments = clean_payments(vec![
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 1),
+                amount: 100.0,
+            },
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 1),
+                amount: 50.0,
+            },
+        ]);
+
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].amount, 150.0);
+    }
+
+    #[test]
+    fn test_clean_payments_drops_near_zero_amounts() {
+        let payments = clean_payments(vec![
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 1),
+                amount: 100.0,
+            },
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 2),
+                amount: 1e-9,
+            },
+        ]);
+
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].date, NaiveDate::from_ymd(2024, 1, 1));
+    }
+
+    #[test]
+    fn test_clean_payments_can_empty_out_entirely() {
+        // Duplicate dates that cancel out, plus straight noise: nothing
+        // survives cleaning.
+        let payments = clean_payments(vec![
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 1),
+                amount: 100.0,
+            },
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 1),
+                amount: -100.0,
+            },
+            Payment {
+                date: NaiveDate::from_ymd(2024, 1, 2),
+                amount: 1e-9,
+            },
+        ]);
+
+        assert!(payments.is_empty());
+    }
+
+    #[test]
+    fn test_npv_discounts_each_amount_by_its_period() {
+        // -100 now, +110 one period later at 10%: exactly breaks even.
+        assert!((npv(0.1, &[-100.0, 110.0])).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_xnpv_matches_npv_for_annual_dates_under_act_365f() {
+        let payments = vec![
+            Payment {
+                date: NaiveDate::from_ymd(2021, 1, 1),
+                amount: -100.0,
+            },
+            Payment {
+                date: NaiveDate::from_ymd(2022, 1, 1),
+                amount: 110.0,
+            },
+        ];
+
+        // 2021 is not a leap year, so the year fraction is exactly 365/365 = 1
+        // and xnpv reduces to npv's integer-period result.
+        let value = xnpv(0.1, &payments, DayCount::Act365F).unwrap();
+        assert!((value - npv(0.1, &[-100.0, 110.0])).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_xnpv_rejects_empty_payments() {
+        assert!(xnpv(0.1, &[], DayCount::Act365F).is_err());
+    }
+
+    #[test]
+    fn test_fv_of_a_lump_sum_matches_compound_interest() {
+        // $1000 today at 10% for 5 periods, no ongoing payments.
+        let value = fv(0.1, 5.0, 0.0, -1000.0, 0);
+        assert!((value - 1610.51).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_fv_and_pv_round_trip() {
+        let value = pv(0.08, 10.0, -150.0, 0.0, 0);
+        let round_tripped = fv(0.08, 10.0, -150.0, value, 0);
+        assert!(round_tripped.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pmt_and_fv_round_trip() {
+        let payment = pmt(0.08, 10.0, 1000.0, 0.0, 0);
+        let value = fv(0.08, 10.0, payment, 1000.0, 0);
+        assert!(value.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nper_and_fv_round_trip() {
+        let periods = nper(0.08, -150.0, 1000.0, 0.0, 0);
+        let value = fv(0.08, periods, -150.0, 1000.0, 0);
+        assert!(value.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rate_and_fv_round_trip() {
+        let found_rate = rate(10.0, -150.0, 1000.0, 0.0, 0, 0.1).unwrap();
+        let value = fv(found_rate, 10.0, -150.0, 1000.0, 0);
+        assert!(value.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mirr_with_known_cash_flows() {
+        // Classic textbook example: invest 120, then 39, 30, 21, 37, 46.
+        let amounts = vec![-120.0, 39.0, 30.0, 21.0, 37.0, 46.0];
+        let value = mirr(&amounts, 0.1, 0.12).unwrap();
+        assert!((value - 0.1260).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_mirr_rejects_all_same_sign_amounts() {
+        assert!(mirr(&[100.0, 200.0, 300.0], 0.1, 0.1).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! `DateLike`: a `FromPyObject` impl that accepts every date representation
+//! users throw at `xirr` — `datetime.date`, ISO strings, integer days since
+//! the Unix epoch, and numpy `datetime64` scalars.
+
+use chrono::{Duration, NaiveDate};
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDateAccess};
+
+pub struct DateLike(pub NaiveDate);
+
+impl<'source> FromPyObject<'source> for DateLike {
+    fn extract(obj: &'source PyAny) -> PyResult<Self> {
+        if let Ok(date) = obj.extract::<&PyDate>() {
+            return Ok(DateLike(NaiveDate::from_ymd(
+                date.get_year(),
+                date.get_month() as u32,
+                date.get_day() as u32,
+            )));
+        }
+
+        if let Ok(s) = obj.extract::<&str>() {
+            return NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map(DateLike)
+                .map_err(|e| exceptions::ValueError::py_err(e.to_string()));
+        }
+
+        if let Ok(days) = obj.extract::<i64>() {
+            return Ok(DateLike(epoch_plus_days(days)));
+        }
+
+        // numpy.datetime64 isn't a Python int, but it supports `__int__`,
+        // which yields its offset from the epoch in the scalar's own unit
+        // (`dtype.name`, e.g. "datetime64[ns]"). Pandas' `.values` commonly
+        // yields `datetime64[ns]`, so the unit can't be assumed to be days.
+        if let Ok(dtype_name) = obj
+            .getattr("dtype")
+            .and_then(|d| d.getattr("name"))
+            .and_then(|n| n.extract::<String>())
+        {
+            if dtype_name.starts_with("datetime64") {
+                let offset = obj.call_method0("__int__")?.extract::<i64>()?;
+                let days = datetime64_offset_to_days(&dtype_name, offset)?;
+                return Ok(DateLike(epoch_plus_days(days)));
+            }
+        }
+
+        Err(exceptions::TypeError::py_err(format!(
+            "cannot interpret {} as a date",
+            obj.get_type().name()
+        )))
+    }
+}
+
+fn epoch_plus_days(days: i64) -> NaiveDate {
+    NaiveDate::from_ymd(1970, 1, 1) + Duration::days(days)
+}
+
+/// Converts a numpy `datetime64[<unit>]` epoch offset into a day count.
+/// `Y` and `M` are rejected since their length in days isn't fixed.
+fn datetime64_offset_to_days(dtype_name: &str, offset: i64) -> PyResult<i64> {
+    let unit = dtype_name
+        .strip_prefix("datetime64[")
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| exceptions::TypeError::py_err(format!("unexpected numpy dtype: {}", dtype_name)))?;
+
+    let per_day: i64 = match unit {
+        "D" => 1,
+        "h" => 24,
+        "m" => 24 * 60,
+        "s" => 24 * 60 * 60,
+        "ms" => 24 * 60 * 60 * 1_000,
+        "us" => 24 * 60 * 60 * 1_000_000,
+        "ns" => 24 * 60 * 60 * 1_000_000_000,
+        "W" => {
+            return Ok(offset * 7);
+        }
+        other => {
+            return Err(exceptions::TypeError::py_err(format!(
+                "unsupported numpy datetime64 unit: {}",
+                other
+            )))
+        }
+    };
+
+    Ok(offset.div_euclid(per_day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::Python;
+
+    #[test]
+    fn test_date_like_from_py_date() {
+        Python::with_gil(|py| {
+            let date = PyDate::new(py, 2024, 3, 15).unwrap();
+            let DateLike(parsed) = date.extract().unwrap();
+            assert_eq!(parsed, NaiveDate::from_ymd(2024, 3, 15));
+        });
+    }
+
+    #[test]
+    fn test_date_like_from_iso_string() {
+        Python::with_gil(|py| {
+            let s = py.eval("'2024-03-15'", None, None).unwrap();
+            let DateLike(parsed) = s.extract().unwrap();
+            assert_eq!(parsed, NaiveDate::from_ymd(2024, 3, 15));
+        });
+    }
+
+    #[test]
+    fn test_date_like_from_epoch_days() {
+        Python::with_gil(|py| {
+            let days = py.eval("12345", None, None).unwrap();
+            let DateLike(parsed) = days.extract().unwrap();
+            assert_eq!(parsed, epoch_plus_days(12345));
+        });
+    }
+
+    #[test]
+    fn test_date_like_from_numpy_datetime64_ns() {
+        Python::with_gil(|py| {
+            // A duck-typed stand-in for numpy.datetime64[ns], so this test
+            // doesn't need numpy installed: just the `dtype.name` and
+            // `__int__` protocol `DateLike` actually relies on.
+            let obj = py
+                .eval(
+                    "type('FakeDatetime64', (), {\
+                        'dtype': type('D', (), {'name': 'datetime64[ns]'})(), \
+                        '__int__': lambda self: 5 * 86_400_000_000_000})()",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let DateLike(parsed) = obj.extract().unwrap();
+            assert_eq!(parsed, epoch_plus_days(5));
+        });
+    }
+}
@@ -0,0 +1,102 @@
+//! Day-count conventions for turning the gap between two dates into a year
+//! fraction, used when discounting XNPV/XIRR cash flows.
+
+use chrono::{Datelike, NaiveDate};
+use pyo3::exceptions;
+use pyo3::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayCount {
+    /// Actual/365 Fixed: actual days divided by 365. The default, and the
+    /// only convention earlier versions of this crate supported.
+    Act365F,
+    /// Actual/360: actual days divided by 360.
+    Act360,
+    /// 30/360 (bond basis): each month treated as 30 days, clamping a day
+    /// of 31 to 30 (the end date's only when the start day is 30 or 31).
+    Thirty360,
+}
+
+impl DayCount {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "act_365f" => Ok(DayCount::Act365F),
+            "act_360" => Ok(DayCount::Act360),
+            "thirty_360" => Ok(DayCount::Thirty360),
+            other => Err(exceptions::ValueError::py_err(format!(
+                "unknown day_count convention: {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn year_fraction(self, start: NaiveDate, end: NaiveDate) -> f64 {
+        match self {
+            DayCount::Act365F => (end - start).num_days() as f64 / 365.0,
+            DayCount::Act360 => (end - start).num_days() as f64 / 360.0,
+            DayCount::Thirty360 => thirty_360_days(start, end) / 360.0,
+        }
+    }
+}
+
+/// `360·(Y₂−Y₁) + 30·(M₂−M₁) + (D₂−D₁)` per the standard 30/360 bond-basis
+/// rule: `D1` is clamped to 30 whenever it's 31, and `D2` is only clamped to
+/// 30 when it's 31 *and* `D1` (after its own clamping) is 30 or 31 — e.g.
+/// 2024-01-15 to 2024-01-31 is 16 days, not 15, since `D1` stays at 15.
+fn thirty_360_days(start: NaiveDate, end: NaiveDate) -> f64 {
+    let d1 = if start.day() == 31 { 30 } else { start.day() };
+    let d2 = if end.day() == 31 && d1 >= 30 { 30 } else { end.day() };
+
+    let d1 = d1 as i64;
+    let d2 = d2 as i64;
+
+    (360 * (end.year() - start.year()) as i64 + 30 * (end.month() as i64 - start.month() as i64)
+        + (d2 - d1)) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_act_365f_uses_actual_days_over_365() {
+        let start = NaiveDate::from_ymd(2024, 1, 1);
+        let end = NaiveDate::from_ymd(2025, 1, 1); // 2024 is a leap year: 366 days.
+        assert_eq!(DayCount::Act365F.year_fraction(start, end), 366.0 / 365.0);
+    }
+
+    #[test]
+    fn test_act_360_uses_actual_days_over_360() {
+        let start = NaiveDate::from_ymd(2024, 1, 1);
+        let end = NaiveDate::from_ymd(2025, 1, 1);
+        assert_eq!(DayCount::Act360.year_fraction(start, end), 366.0 / 360.0);
+    }
+
+    #[test]
+    fn test_thirty_360_does_not_clamp_d2_when_d1_is_not_a_month_end() {
+        // D1 = 15 isn't 30/31, so D2 stays 31: 16 days, not 15.
+        let start = NaiveDate::from_ymd(2024, 1, 15);
+        let end = NaiveDate::from_ymd(2024, 1, 31);
+        assert_eq!(DayCount::Thirty360.year_fraction(start, end), 16.0 / 360.0);
+    }
+
+    #[test]
+    fn test_thirty_360_full_month() {
+        let start = NaiveDate::from_ymd(2024, 1, 1);
+        let end = NaiveDate::from_ymd(2024, 2, 1);
+        assert_eq!(DayCount::Thirty360.year_fraction(start, end), 30.0 / 360.0);
+    }
+
+    #[test]
+    fn test_thirty_360_clamps_d2_when_d1_is_a_month_end() {
+        // D1 = 31 clamps to 30, which makes D2 = 31 clamp to 30 as well.
+        let start = NaiveDate::from_ymd(2024, 1, 31);
+        let end = NaiveDate::from_ymd(2024, 3, 31);
+        assert_eq!(DayCount::Thirty360.year_fraction(start, end), 60.0 / 360.0);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_convention() {
+        assert!(DayCount::parse("bogus").is_err());
+    }
+}
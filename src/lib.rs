@@ -1,34 +1,229 @@
-use chrono::NaiveDate;
 use pyo3::prelude::*;
-use pyo3::types::{PyDate, PyDateAccess, PyList};
+use pyo3::types::PyList;
 use pyo3::{create_exception, exceptions, wrap_pyfunction};
-use xirr::{self, Payment};
+use xirr::Payment;
+
+mod conversions;
+mod core;
+mod dates;
+mod day_count;
+
+use conversions::to_payments;
+use day_count::DayCount;
 
 create_exception!(pyxirr, InvalidPaymentsError, exceptions::Exception);
 
+fn to_err(e: core::FinanceError) -> PyErr {
+    InvalidPaymentsError::py_err(e.to_string())
+}
+
+fn parse_day_count(day_count: Option<&str>) -> PyResult<DayCount> {
+    match day_count {
+        Some(name) => DayCount::parse(name),
+        None => Ok(DayCount::Act365F),
+    }
+}
+
+/// Computes XIRR given either two parallel sequences (`xirr(dates,
+/// amounts)`), a single dict mapping date -> amount, or a single iterable of
+/// `(date, amount)` pairs (e.g. `zip(dates, amounts)`). `guess` seeds the
+/// solver's initial rate. `day_count` selects the year-fraction convention
+/// ("act_365f", the default; "act_360"; or "thirty_360").
+///
+/// When `silent` is true, a solver that fails to converge (or lands on NaN)
+/// returns `None` instead of raising, which is handy for batch processing
+/// (e.g. one XIRR per DataFrame row) where a single bad row shouldn't abort
+/// the whole computation. Structurally invalid input (empty payments, no
+/// sign change to bracket) still raises `InvalidPaymentsError` either way.
+#[pyfunction]
+#[args(guess = "0.1", silent = "false", day_count = "None")]
+fn xirr_rust(
+    arg1: &PyAny,
+    arg2: Option<&PyAny>,
+    guess: f64,
+    silent: bool,
+    day_count: Option<&str>,
+) -> PyResult<Option<f64>> {
+    let day_count = parse_day_count(day_count)?;
+    let payments = to_payments(arg1, arg2)?;
+
+    match core::xirr(&payments, guess, day_count) {
+        Ok(rate) if rate.is_nan() => {
+            if silent {
+                Ok(None)
+            } else {
+                Err(InvalidPaymentsError::py_err("xirr produced NaN"))
+            }
+        }
+        Ok(rate) => Ok(Some(rate)),
+        Err(core::FinanceError::DidNotConverge(_)) if silent => Ok(None),
+        Err(e) => Err(InvalidPaymentsError::py_err(e.to_string())),
+    }
+}
+
+/// A computed rate with a larger magnitude than this is treated as a
+/// numerical artifact rather than a real answer by `xirr_clean`.
+const MAX_PLAUSIBLE_RATE: f64 = 100.0;
+
+/// Lenient XIRR: aggregates payments that share a date, drops amounts too
+/// small to matter, and returns `None` instead of raising when nothing is
+/// left after cleaning, when the solver fails to converge, or when the rate
+/// is NaN or implausibly large. Payments with no sign change to bracket
+/// still raise `InvalidPaymentsError`. Intended for batch/dirty data where
+/// `xirr_rust` would be too strict. `day_count` is as in `xirr_rust`.
+#[pyfunction]
+#[args(guess = "0.1", day_count = "None")]
+fn xirr_clean(arg1: &PyAny, arg2: Option<&PyAny>, guess: f64, day_count: Option<&str>) -> PyResult<Option<f64>> {
+    let day_count = parse_day_count(day_count)?;
+    let payments = core::clean_payments(to_payments(arg1, arg2)?);
+
+    if payments.is_empty() {
+        return Ok(None);
+    }
+
+    match core::xirr(&payments, guess, day_count) {
+        Ok(rate) if rate.is_nan() || rate.abs() > MAX_PLAUSIBLE_RATE => Ok(None),
+        Ok(rate) => Ok(Some(rate)),
+        Err(core::FinanceError::DidNotConverge(_)) => Ok(None),
+        Err(e) => Err(InvalidPaymentsError::py_err(e.to_string())),
+    }
+}
+
+fn extract_payments(dates: &PyList, amounts: &[f64]) -> PyResult<Vec<Payment>> {
+    dates
+        .into_iter()
+        .zip(amounts.iter())
+        .map(|(py_date, &amount)| {
+            Ok(Payment {
+                date: conversions::extract_date(py_date)?,
+                amount,
+            })
+        })
+        .collect()
+}
+
+/// Net present value of `amounts`, one per integer period starting at t = 0.
+#[pyfunction]
+fn npv(rate: f64, amounts: Vec<f64>) -> f64 {
+    core::npv(rate, &amounts)
+}
+
+/// Net present value of `amounts` dated by the parallel `dates` sequence.
+/// `day_count` selects the year-fraction convention, as in `xirr_rust`.
+#[pyfunction]
+#[args(day_count = "None")]
+fn xnpv(rate: f64, dates: &PyList, amounts: Vec<f64>, day_count: Option<&str>) -> PyResult<f64> {
+    let day_count = parse_day_count(day_count)?;
+    let payments = extract_payments(dates, &amounts)?;
+    core::xnpv(rate, &payments, day_count).map_err(to_err)
+}
+
+/// Rate at which the net present value of `amounts` is zero.
 #[pyfunction]
-fn xirr_rust(py_payments: &PyList) -> PyResult<f64> {
-    let mut payments = Vec::with_capacity(py_payments.len());
+fn irr(amounts: Vec<f64>) -> PyResult<f64> {
+    core::irr(&amounts).map_err(to_err)
+}
+
+/// Future value of a series of equal payments of `pmt`, given a starting
+/// value `pv`. `pay_type` is 0 for end-of-period payments, 1 for
+/// beginning-of-period.
+#[pyfunction]
+#[args(pv = "0.0", pay_type = "0")]
+fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, pay_type: i32) -> f64 {
+    core::fv(rate, nper, pmt, pv, pay_type)
+}
+
+/// Present value of a series of equal payments of `pmt`, given a final
+/// value `fv`. `pay_type` is 0 for end-of-period payments, 1 for
+/// beginning-of-period.
+#[pyfunction]
+#[args(fv = "0.0", pay_type = "0")]
+fn pv(rate: f64, nper: f64, pmt: f64, fv: f64, pay_type: i32) -> f64 {
+    core::pv(rate, nper, pmt, fv, pay_type)
+}
+
+/// Payment per period for a loan/annuity with the given `rate` and `nper`.
+#[pyfunction]
+#[args(fv = "0.0", pay_type = "0")]
+fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, pay_type: i32) -> f64 {
+    core::pmt(rate, nper, pv, fv, pay_type)
+}
+
+/// Number of periods for a loan/annuity with the given `rate` and `pmt`.
+#[pyfunction]
+#[args(fv = "0.0", pay_type = "0")]
+fn nper(rate: f64, pmt: f64, pv: f64, fv: f64, pay_type: i32) -> f64 {
+    core::nper(rate, pmt, pv, fv, pay_type)
+}
+
+/// Interest rate per period for a loan/annuity; found numerically since it
+/// has no closed form.
+#[pyfunction]
+#[args(pv = "0.0", fv = "0.0", pay_type = "0", guess = "0.1")]
+fn rate(nper: f64, pmt: f64, pv: f64, fv: f64, pay_type: i32, guess: f64) -> PyResult<f64> {
+    core::rate(nper, pmt, pv, fv, pay_type, guess).map_err(to_err)
+}
+
+/// Modified internal rate of return, discounting outflows at `finance_rate`
+/// and reinvesting inflows at `reinvest_rate`.
+#[pyfunction]
+fn mirr(amounts: Vec<f64>, finance_rate: f64, reinvest_rate: f64) -> PyResult<f64> {
+    core::mirr(&amounts, finance_rate, reinvest_rate).map_err(to_err)
+}
 
-    for py_elem in py_payments.into_iter() {
-        let date = py_elem.get_item(0).unwrap().extract::<&PyDate>()?;
-        let amount = py_elem.get_item(1).unwrap().extract::<f64>()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::Python;
 
-        payments.push(Payment {
-            date: NaiveDate::from_ymd(
-                date.get_year(),
-                date.get_month() as u32,
-                date.get_day() as u32,
-            ),
-            amount: amount,
+    #[test]
+    fn test_xirr_rust_silences_nan_when_silent() {
+        Python::with_gil(|py| {
+            // A single payment of 0.0 makes npv(r) == 0 for every r, so
+            // Newton accepts the very first iterate it tries — including a
+            // NaN guess, which is therefore returned verbatim as the "rate".
+            let dates = py.eval("['2024-01-01']", None, None).unwrap();
+            let amounts = py.eval("[0.0]", None, None).unwrap();
+
+            let result = xirr_rust(dates, Some(amounts), f64::NAN, true, None).unwrap();
+            assert_eq!(result, None);
         });
     }
 
-    let res = xirr::compute(&payments);
+    #[test]
+    fn test_xirr_rust_raises_on_nan_when_not_silent() {
+        Python::with_gil(|py| {
+            let dates = py.eval("['2024-01-01']", None, None).unwrap();
+            let amounts = py.eval("[0.0]", None, None).unwrap();
 
-    match res {
-        Err(e) => Err(InvalidPaymentsError::py_err(e.to_string())),
-        Ok(v) => Ok(v),
+            let result = xirr_rust(dates, Some(amounts), f64::NAN, false, None);
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn test_xirr_rust_still_raises_on_invalid_input_when_silent() {
+        Python::with_gil(|py| {
+            // Same-signed payments never bracket a root, which xirr reports
+            // as structurally invalid input rather than non-convergence —
+            // `silent` only suppresses the latter.
+            let dates = py.eval("['2024-01-01', '2024-06-01']", None, None).unwrap();
+            let amounts = py.eval("[100.0, 200.0]", None, None).unwrap();
+
+            let result = xirr_rust(dates, Some(amounts), 0.1, true, None);
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn test_xirr_rust_raises_on_empty_payments_regardless_of_silent() {
+        Python::with_gil(|py| {
+            let dates = py.eval("[]", None, None).unwrap();
+            let amounts = py.eval("[]", None, None).unwrap();
+
+            assert!(xirr_rust(dates, Some(amounts), 0.1, true, None).is_err());
+            assert!(xirr_rust(dates, Some(amounts), 0.1, false, None).is_err());
+        });
     }
 }
 
@@ -36,6 +231,16 @@ fn xirr_rust(py_payments: &PyList) -> PyResult<f64> {
 /// A Python module implemented in Rust.
 fn pyxirr(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(xirr_rust))?;
+    m.add_wrapped(wrap_pyfunction!(xirr_clean))?;
+    m.add_wrapped(wrap_pyfunction!(npv))?;
+    m.add_wrapped(wrap_pyfunction!(xnpv))?;
+    m.add_wrapped(wrap_pyfunction!(irr))?;
+    m.add_wrapped(wrap_pyfunction!(fv))?;
+    m.add_wrapped(wrap_pyfunction!(pv))?;
+    m.add_wrapped(wrap_pyfunction!(pmt))?;
+    m.add_wrapped(wrap_pyfunction!(nper))?;
+    m.add_wrapped(wrap_pyfunction!(rate))?;
+    m.add_wrapped(wrap_pyfunction!(mirr))?;
 
     Ok(())
 }